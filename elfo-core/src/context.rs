@@ -0,0 +1,179 @@
+use std::{
+    future::poll_fn,
+    sync::Arc,
+    task::{self, Poll},
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    addr::Addr,
+    dataspace::Dataspace,
+    envelope::{Envelope, MessageKind},
+    mailbox::Mailbox,
+    message::{AnyMessage, Message},
+    object::AddressBook,
+    scope,
+    source::SourceSet,
+    ResponseToken,
+};
+
+/// A pollable producer of envelopes attached to a [`Context`] via
+/// [`Context::with`], e.g. a timer or a [`Stream`](crate::stream::Stream).
+pub trait Source {
+    /// Polls the source for the next envelope, registering `cx`'s waker.
+    fn poll_recv(&self, cx: &mut task::Context<'_>) -> Poll<Option<Envelope>>;
+}
+
+/// An actor's handle to the runtime: its mailbox, attached sources, config and
+/// the address book used to route outbound messages.
+pub struct Context<C = ()> {
+    actor: Addr,
+    group: Addr,
+    book: AddressBook,
+    config: Arc<C>,
+    mailbox: Mailbox,
+    sources: SourceSet,
+    dataspace: Arc<Mutex<Dataspace>>,
+}
+
+impl<C: 'static> Context<C> {
+    /// Receives the next envelope from the mailbox or an attached source,
+    /// returning `None` once the actor is stopping and drained.
+    ///
+    /// Before handing the envelope back it installs the envelope's causal trace
+    /// context into the current scope (see
+    /// [`Envelope::install_trace`](crate::envelope::Envelope)), so the scope
+    /// adopts the envelope's trace id and records its span id as the new
+    /// parent. Messages the actor sends while handling it therefore continue
+    /// the same causal tree instead of starting a disconnected one.
+    ///
+    /// Also opens an [`otel::envelope_span`](crate::otel::envelope_span) for
+    /// the dequeue itself, named after the message, so the OTLP bridge has a
+    /// real span to link the envelope's trace/parent-span context to. The span
+    /// can't stay entered across the `.await`s in the caller's handler (its
+    /// guard is `!Send`, and `Context` itself is sent into spawned tasks), so
+    /// it only covers this synchronous tail of `recv` — still enough to join
+    /// every processed envelope's scope to the exported trace.
+    pub async fn recv(&mut self) -> Option<Envelope> {
+        let envelope = poll_fn(|cx| self.poll_next(cx)).await?;
+        scope::with(|scope| envelope.install_trace(scope));
+
+        let span = crate::otel::envelope_span(envelope.message().name());
+        let _enter = span.enter();
+
+        Some(envelope)
+    }
+
+    /// Polls the mailbox first, then each attached source in turn.
+    fn poll_next(&mut self, cx: &mut task::Context<'_>) -> Poll<Option<Envelope>> {
+        if let Poll::Ready(envelope) = self.mailbox.poll_recv(cx) {
+            return Poll::Ready(envelope);
+        }
+        self.sources.poll_recv(cx)
+    }
+
+    /// The current group configuration.
+    #[inline]
+    pub fn config(&self) -> &C {
+        &self.config
+    }
+
+    /// This actor's address.
+    #[inline]
+    pub fn addr(&self) -> Addr {
+        self.actor
+    }
+
+    /// This actor's group address.
+    #[inline]
+    pub fn group(&self) -> Addr {
+        self.group
+    }
+
+    /// Whether a coordinated shutdown is in progress for this group.
+    ///
+    /// Once this returns `true` a [`Terminate`](crate::shutdown::Terminate) has
+    /// been (or is about to be) delivered; actors should finish in-flight work
+    /// and stop. Delegates to the group's shutdown token via the scope.
+    #[inline]
+    pub fn is_terminating(&self) -> bool {
+        scope::is_terminating()
+    }
+
+    /// Sends a one-way message, routed by the group's topology.
+    pub async fn send<M: Message>(&self, message: M) -> Result<(), crate::errors::SendError<M>> {
+        let envelope = Envelope::new(message, MessageKind::Regular { sender: self.actor });
+        self.book.send(self.group, envelope).await
+    }
+
+    /// Sends a one-way message to a specific address (used by the network
+    /// transport to deliver a decoded frame to a bound local actor).
+    pub async fn send_to<M: Message>(
+        &self,
+        recipient: Addr,
+        message: M,
+    ) -> Result<(), crate::errors::SendError<M>> {
+        let envelope = Envelope::new(message, MessageKind::Regular { sender: self.actor });
+        self.book.send(recipient, envelope).await
+    }
+
+    /// Best-effort synchronous send to a specific address, dropping the message
+    /// if the mailbox is full or closed. Used to fan dataspace
+    /// [`Asserted`](crate::dataspace::Asserted)/[`Retracted`](crate::dataspace::Retracted)
+    /// notifications out to observers without awaiting.
+    pub(crate) fn try_send_to<M: Message>(&self, recipient: Addr, message: M) {
+        let envelope = Envelope::new(message, MessageKind::Regular { sender: self.actor });
+        self.book.try_send(recipient, envelope.upcast());
+    }
+
+    /// Sends `message` as a request to a specific address and awaits its single
+    /// response.
+    ///
+    /// Used by the network transport to forward a tunneled request into the
+    /// bound local actor and relay the answer back across the connection; it is
+    /// the directed counterpart of [`request`](Self::request), which fans out
+    /// over the group's topology.
+    pub async fn request_to(
+        &self,
+        recipient: Addr,
+        message: AnyMessage,
+    ) -> Result<AnyMessage, crate::errors::RequestError> {
+        let (token, rx) = ResponseToken::new();
+        let envelope = Envelope::new(message, MessageKind::RequestAny(token));
+        self.book.send(recipient, envelope).await?;
+        rx.await.map_err(|_| crate::errors::RequestError::Closed)
+    }
+
+    /// Answers a request by resolving its [`ResponseToken`].
+    ///
+    /// The transport calls this when a tunneled response frame arrives, so the
+    /// originating `ctx.request(..).resolve()` on the far node completes.
+    pub fn respond(&self, token: ResponseToken, message: AnyMessage) {
+        self.book.respond(token, message);
+    }
+
+    /// A detached clone of this context for use in a spawned task.
+    ///
+    /// Shares the address book, config and dataspace so the clone routes and
+    /// observes exactly as the actor would, but gets its own empty mailbox and
+    /// source set — it is used only to *send*, never to `recv`. This is how the
+    /// network transport hands a context into its per-connection tasks.
+    pub fn pruned(&self) -> Self {
+        Self {
+            actor: self.actor,
+            group: self.group,
+            book: self.book.clone(),
+            config: self.config.clone(),
+            mailbox: Mailbox::default(),
+            sources: SourceSet::default(),
+            dataspace: self.dataspace.clone(),
+        }
+    }
+
+    /// The group's shared dataspace of standing facts.
+    #[inline]
+    pub(crate) fn dataspace(&self) -> &Arc<Mutex<Dataspace>> {
+        &self.dataspace
+    }
+}
@@ -0,0 +1,277 @@
+//! A publish/observe dataspace with automatic retraction on actor exit.
+//!
+//! In addition to point-to-point `send`/`request`/`respond`, an actor can
+//! publish a standing *fact* into its group's dataspace with
+//! [`Context::assert`](crate::context::Context::assert) and receive back a
+//! [`Handle`]. Other actors [`observe`](crate::context::Context::observe) a
+//! pattern and are delivered an [`Asserted`] message for every matching fact
+//! that currently holds (and for each one asserted later), plus a
+//! [`Retracted`] message once a fact goes away.
+//!
+//! The defining invariant is that **every assertion is retracted when the
+//! asserting actor terminates** — normally or via panic. The dataspace tracks
+//! outstanding handles per actor [`Addr`], and the supervisor calls
+//! [`Dataspace::retract_all`] when the actor object is dropped, so observers
+//! always converge on a consistent view without manual cleanup.
+
+use std::{any::TypeId, collections::hash_map::Entry, sync::Arc};
+
+use fxhash::FxHashMap;
+use parking_lot::Mutex;
+
+use elfo_macros::message;
+
+use crate::{
+    addr::Addr,
+    context::Context,
+    message::{AnyMessage, Message},
+};
+
+/// An opaque identifier for a single assertion.
+///
+/// Returned by `assert` and echoed in the [`Asserted`]/[`Retracted`] messages
+/// so an observer can correlate the appearance and the disappearance of a
+/// fact. Unique within a dataspace for the lifetime of the assertion.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Handle(u64);
+
+impl Handle {
+    #[inline]
+    pub(crate) fn new(raw: u64) -> Self {
+        Handle(raw)
+    }
+}
+
+/// Delivered to observers when a fact matching their pattern appears.
+#[message(elfo = crate)]
+#[non_exhaustive]
+pub struct Asserted<T> {
+    /// The published value.
+    pub value: T,
+    /// The handle of the assertion, echoed by its eventual [`Retracted`].
+    pub handle: Handle,
+}
+
+/// Delivered to observers when a previously [`Asserted`] fact goes away, either
+/// because the asserting actor retracted it or because it terminated.
+#[message(elfo = crate)]
+#[non_exhaustive]
+pub struct Retracted {
+    /// The handle of the assertion that no longer holds.
+    pub handle: Handle,
+}
+
+struct Fact {
+    owner: Addr,
+    type_id: TypeId,
+    value: AnyMessage,
+}
+
+/// The per-group store of standing facts.
+///
+/// Owned by the group supervisor and shared with each actor's [`Context`]; the
+/// supervisor consults it to fan [`Asserted`]/[`Retracted`] out to matching
+/// observers and to retract an actor's facts when it is dropped.
+#[derive(Default)]
+pub(crate) struct Dataspace {
+    next: u64,
+    facts: FxHashMap<Handle, Fact>,
+    by_owner: FxHashMap<Addr, Vec<Handle>>,
+    observers: FxHashMap<TypeId, Vec<Addr>>,
+    // Reverse index of `observers`, so `retract_all` can drop a terminated
+    // actor's entries there too instead of only pruning its own facts —
+    // without it, `observers` would grow a dead address for every type a
+    // restarted actor ever observed, and every future `assert` of that type
+    // would keep best-effort-sending into a mailbox nothing reads anymore.
+    observed_by: FxHashMap<Addr, Vec<TypeId>>,
+}
+
+impl Dataspace {
+    /// Records a new fact of type `T` asserted by `owner`, returning its handle
+    /// and the observers currently interested in `T`.
+    pub(crate) fn assert<T: Message>(&mut self, owner: Addr, value: T) -> (Handle, Vec<Addr>) {
+        let handle = Handle::new(self.next);
+        self.next += 1;
+
+        let type_id = TypeId::of::<T>();
+        self.facts.insert(
+            handle,
+            Fact {
+                owner,
+                type_id,
+                value: value.upcast(),
+            },
+        );
+        self.by_owner.entry(owner).or_default().push(handle);
+
+        (handle, self.observers_of(type_id))
+    }
+
+    /// Registers `observer` as interested in facts of type `T`.
+    pub(crate) fn register_observer<T: Message>(&mut self, observer: Addr) {
+        let type_id = TypeId::of::<T>();
+        self.observers.entry(type_id).or_default().push(observer);
+        self.observed_by.entry(observer).or_default().push(type_id);
+    }
+
+    /// Retracts a single fact, if the handle is still live and owned by
+    /// `owner`. Returns the observers to notify, or `None` if nothing matched.
+    pub(crate) fn retract(&mut self, owner: Addr, handle: Handle) -> Option<Vec<Addr>> {
+        match self.facts.entry(handle) {
+            Entry::Occupied(entry) if entry.get().owner == owner => {
+                let fact = entry.remove();
+                if let Some(handles) = self.by_owner.get_mut(&owner) {
+                    handles.retain(|h| *h != handle);
+                }
+                Some(self.observers_of(fact.type_id))
+            }
+            _ => None,
+        }
+    }
+
+    /// Retracts every fact asserted by `owner`, returning each handle with the
+    /// observers to notify so the supervisor can broadcast a [`Retracted`].
+    ///
+    /// Also drops `owner` from every `observers` list it registered into, so a
+    /// dead actor's address doesn't linger there forever across restarts,
+    /// silently swallowing `Asserted` notifications for good.
+    ///
+    /// Called from the supervisor when the actor object is dropped, which is
+    /// what makes retraction automatic on both normal exit and panic.
+    pub(crate) fn retract_all(&mut self, owner: Addr) -> Vec<(Handle, Vec<Addr>)> {
+        let handles = self.by_owner.remove(&owner).unwrap_or_default();
+        let retracted = handles
+            .into_iter()
+            .filter_map(|handle| {
+                let fact = self.facts.remove(&handle)?;
+                Some((handle, self.observers_of(fact.type_id)))
+            })
+            .collect();
+
+        for type_id in self.observed_by.remove(&owner).unwrap_or_default() {
+            if let Some(observers) = self.observers.get_mut(&type_id) {
+                observers.retain(|&observer| observer != owner);
+            }
+        }
+
+        retracted
+    }
+
+    /// Replays the currently held facts matching `T` for a freshly registered
+    /// observer, so it starts from a consistent view.
+    pub(crate) fn replay<T: Message + Clone>(&self) -> Vec<(Handle, T)> {
+        self.facts
+            .iter()
+            .filter_map(|(handle, fact)| {
+                fact.value
+                    .downcast_ref::<T>()
+                    .map(|value| (*handle, value.clone()))
+            })
+            .collect()
+    }
+
+    fn observers_of(&self, type_id: TypeId) -> Vec<Addr> {
+        self.observers.get(&type_id).cloned().unwrap_or_default()
+    }
+}
+
+impl<C: 'static> Context<C> {
+    /// Publishes a standing fact into the group's dataspace and returns its
+    /// [`Handle`].
+    ///
+    /// Observers of `T` are immediately delivered an [`Asserted`]. The fact is
+    /// **automatically retracted when this actor terminates** (normally or via
+    /// panic), so observers never see a stale view — see
+    /// [`Dataspace::retract_all`].
+    pub fn assert<T: Message + Clone>(&self, value: T) -> Handle {
+        let owner = self.addr();
+        let (handle, observers) = self.dataspace().lock().assert(owner, value.clone());
+
+        for observer in observers {
+            self.try_send_to(
+                observer,
+                Asserted {
+                    value: value.clone(),
+                    handle,
+                },
+            );
+        }
+        handle
+    }
+
+    /// Withdraws a single previously-[`assert`](Self::assert)ed fact, notifying
+    /// observers with a [`Retracted`] right away instead of waiting for the
+    /// automatic retraction on actor exit.
+    ///
+    /// A no-op if `handle` is unknown or was asserted by a different actor.
+    pub fn retract(&self, handle: Handle) {
+        let owner = self.addr();
+        let Some(observers) = self.dataspace().lock().retract(owner, handle) else {
+            return;
+        };
+
+        for observer in observers {
+            self.try_send_to(observer, Retracted { handle });
+        }
+    }
+
+    /// Registers interest in facts of type `T`.
+    ///
+    /// Facts that already hold are replayed as [`Asserted`] messages right
+    /// away, and every later assertion/retraction of a `T` is delivered as it
+    /// happens, so `msg!`-matching on `Asserted`/`Retracted` reflects the live
+    /// dataspace.
+    pub fn observe<T: Message + Clone>(&self) {
+        let observer = self.addr();
+
+        let replay = {
+            let mut dataspace = self.dataspace().lock();
+            dataspace.register_observer::<T>(observer);
+            dataspace.replay::<T>()
+        };
+
+        for (handle, value) in replay {
+            self.try_send_to(observer, Asserted { value, handle });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[message(elfo = crate)]
+    struct Fact1(u32);
+
+    #[test]
+    fn retract_all_drops_owned_facts() {
+        let mut ds = Dataspace::default();
+        let (handle, observers) = ds.assert(Addr::NULL, Fact1(1));
+        assert!(observers.is_empty());
+
+        let retracted = ds.retract_all(Addr::NULL);
+        assert_eq!(retracted, vec![(handle, vec![])]);
+        assert!(ds.retract(Addr::NULL, handle).is_none());
+    }
+
+    #[test]
+    fn retract_all_prunes_observer_registrations() {
+        let mut ds = Dataspace::default();
+        ds.register_observer::<Fact1>(Addr::NULL);
+        assert_eq!(ds.observers_of(TypeId::of::<Fact1>()), vec![Addr::NULL]);
+
+        ds.retract_all(Addr::NULL);
+
+        // The dropped actor must not keep receiving `Asserted` for types it
+        // used to observe.
+        assert!(ds.observers_of(TypeId::of::<Fact1>()).is_empty());
+    }
+
+    #[test]
+    fn retract_is_a_noop_for_unknown_handle() {
+        let mut ds = Dataspace::default();
+        let (handle, _) = ds.assert(Addr::NULL, Fact1(1));
+
+        assert!(ds.retract(Addr::NULL, Handle::new(handle.0 + 1)).is_none());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    addr::Addr,
+    message::{AnyMessage, Message},
+    scope::{self, Scope, SpanId},
+    trace_id::{self, TraceId},
+    ResponseToken,
+};
+
+/// The causal trace context stamped onto every envelope.
+///
+/// `trace_id` identifies the whole causal tree (inherited from the sending
+/// scope), `span_id` names this single envelope, and `parent_span` is the span
+/// of the envelope the sender was processing when it produced this one. A
+/// fan-out therefore forms a tree rooted at the originating external event.
+#[derive(Debug, Clone, Copy)]
+pub struct TraceContext {
+    pub trace_id: TraceId,
+    pub span_id: SpanId,
+    pub parent_span: Option<SpanId>,
+}
+
+impl TraceContext {
+    /// Captures the context for a freshly created envelope from the current
+    /// scope: the scope's trace id, a freshly minted span, and the scope's
+    /// current span as the parent. Outside the actor system (e.g. a source
+    /// producing the very first envelope) a new trace is rooted.
+    fn stamp() -> Self {
+        Self {
+            trace_id: scope::try_trace_id().unwrap_or_else(trace_id::generate),
+            span_id: trace_id::generate(),
+            parent_span: scope::try_with(Scope::parent_span).flatten(),
+        }
+    }
+}
+
+/// A message in transit together with how it should be delivered.
+pub struct Envelope<M = AnyMessage> {
+    message: M,
+    kind: MessageKind,
+    trace: TraceContext,
+}
+
+/// How an envelope should be delivered on the receiving end.
+pub enum MessageKind {
+    /// A one-way message; `sender` is [`Addr::NULL`] for source-produced ones.
+    Regular { sender: Addr },
+    /// A request expecting a single response, answered via `token`.
+    RequestAny(ResponseToken),
+    /// A request fanned out to every routee, answered via `token`.
+    RequestAll(ResponseToken),
+}
+
+impl<M> Envelope<M> {
+    /// Creates an envelope, stamping the current causal trace context onto it
+    /// (see [`TraceContext`]). This is where every envelope's span id is
+    /// minted, so every produced message joins the sender's trace.
+    pub fn new(message: M, kind: MessageKind) -> Self {
+        Self {
+            message,
+            kind,
+            trace: TraceContext::stamp(),
+        }
+    }
+
+    /// The causal trace context stamped when this envelope was created.
+    #[inline]
+    pub fn trace(&self) -> TraceContext {
+        self.trace
+    }
+
+    #[inline]
+    pub fn message(&self) -> &M {
+        &self.message
+    }
+
+    #[inline]
+    pub fn message_kind(&self) -> &MessageKind {
+        &self.kind
+    }
+}
+
+impl<M: Message> Envelope<M> {
+    /// Erases the concrete message type for storage in a mailbox.
+    pub fn upcast(self) -> Envelope {
+        Envelope {
+            message: self.message.upcast(),
+            kind: self.kind,
+            trace: self.trace,
+        }
+    }
+}
+
+impl Envelope {
+    /// Installs this envelope's trace context into `scope` as the actor dequeues
+    /// it: the scope adopts the envelope's trace id and records its span id as
+    /// the new parent, so messages the actor now sends continue the chain.
+    ///
+    /// Called from the dequeue path in [`Context::recv`](crate::context::Context::recv).
+    pub(crate) fn install_trace(&self, scope: &Scope) {
+        scope.set_trace_id(self.trace.trace_id);
+        scope.set_parent_span(self.trace.span_id);
+    }
+}
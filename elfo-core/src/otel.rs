@@ -0,0 +1,72 @@
+//! Optional bridge from elfo's causal trace context to
+//! [`tracing-opentelemetry`], so traces can be shipped over OTLP.
+//!
+//! For every processed envelope an actor loop can open one span via
+//! [`envelope_span`]; the span is linked to the envelope's parent span context
+//! (see [`scope::parent_span`]) so the exported trace mirrors the in-process
+//! causal tree rooted at the originating external event.
+//!
+//! Only the OpenTelemetry parent linking is gated behind the
+//! `tracing-opentelemetry` feature, because it pulls in the OpenTelemetry SDK;
+//! without it [`envelope_span`] still opens a plain `tracing` span carrying
+//! elfo's own trace and parent span ids, but nothing links it for OTLP export.
+
+use tracing::{span, Span};
+
+use crate::scope;
+
+/// Opens a span for the envelope currently being processed, linked to the
+/// parent span recorded in the scope.
+///
+/// The returned [`Span`] should be entered for the duration of the handler so
+/// that nested `tracing` events and `tokio` tasks attach to it. When the
+/// `tracing-opentelemetry` layer is installed the span is exported over OTLP
+/// with its parent set to [`scope::parent_span`], keeping remote and local
+/// spans joined through the shared trace id.
+pub fn envelope_span(name: &'static str) -> Span {
+    let trace_id = scope::trace_id();
+    let parent = scope::parent_span();
+
+    // `parent_span` is recorded after the fact because `Option<u64>` is not a
+    // `tracing::Value`; the field stays empty for a root envelope.
+    let span = span!(
+        tracing::Level::INFO,
+        "envelope",
+        otel.name = name,
+        trace_id = %u64::from(trace_id),
+        parent_span = tracing::field::Empty,
+    );
+
+    if let Some(parent) = parent {
+        span.record("parent_span", u64::from(parent));
+    }
+
+    #[cfg(feature = "tracing-opentelemetry")]
+    if let Some(parent) = parent {
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        span.set_parent(remote_context(trace_id, parent));
+    }
+
+    span
+}
+
+/// Builds an OpenTelemetry context referencing a remote parent span so the
+/// exported span links back to it instead of starting a new trace.
+#[cfg(feature = "tracing-opentelemetry")]
+fn remote_context(
+    trace_id: crate::trace_id::TraceId,
+    parent: scope::SpanId,
+) -> opentelemetry::Context {
+    use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceId, TraceState};
+
+    let span_context = SpanContext::new(
+        TraceId::from_u128(u128::from(u64::from(trace_id))),
+        SpanId::from_u64(u64::from(parent)),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+
+    opentelemetry::Context::new().with_remote_span_context(span_context)
+}
@@ -6,6 +6,7 @@ use crate::{
     addr::Addr,
     object::ObjectMeta,
     permissions::{AtomicPermissions, Permissions},
+    shutdown::ShutdownToken,
     trace_id::{self, TraceId},
 };
 
@@ -13,16 +14,25 @@ tokio::task_local! {
     static SCOPE: Scope;
 }
 
+/// A span identifier within a trace.
+///
+/// Shares the representation and the generator with [`TraceId`] (a non-zero
+/// 64-bit value), but denotes a single processed envelope rather than the whole
+/// causal tree it belongs to.
+pub type SpanId = TraceId;
+
 #[derive(Clone)]
 pub struct Scope {
     actor: Addr,
     group: Addr,
     meta: Arc<ObjectMeta>,
     trace_id: Cell<TraceId>,
+    parent: Cell<Option<SpanId>>,
 
     // Per group.
     permissions: Arc<AtomicPermissions>,
     logging_limiter: Arc<RateLimiter>,
+    shutdown: ShutdownToken,
 }
 
 assert_impl_all!(Scope: Send);
@@ -37,6 +47,7 @@ impl Scope {
         meta: Arc<ObjectMeta>,
         perm: Arc<AtomicPermissions>,
         logging_limiter: Arc<RateLimiter>,
+        shutdown: ShutdownToken,
     ) -> Self {
         Self::with_trace_id(
             trace_id::generate(),
@@ -45,6 +56,7 @@ impl Scope {
             meta,
             perm,
             logging_limiter,
+            shutdown,
         )
     }
 
@@ -57,14 +69,17 @@ impl Scope {
         meta: Arc<ObjectMeta>,
         permissions: Arc<AtomicPermissions>,
         logging_limiter: Arc<RateLimiter>,
+        shutdown: ShutdownToken,
     ) -> Self {
         Self {
             actor,
             group,
             meta,
             trace_id: Cell::new(trace_id),
+            parent: Cell::new(None),
             permissions,
             logging_limiter,
+            shutdown,
         }
     }
 
@@ -102,6 +117,42 @@ impl Scope {
         self.trace_id.set(trace_id);
     }
 
+    /// Returns the span id of the envelope that installed this scope, if any.
+    ///
+    /// It is `None` for scopes rooted at an external event (e.g. an incoming
+    /// request) and `Some` once an actor dequeues an envelope stamped by a
+    /// preceding actor. New envelopes produced within this scope use it as
+    /// their parent, so a fan-out forms a causal tree.
+    #[inline]
+    pub fn parent_span(&self) -> Option<SpanId> {
+        self.parent.get()
+    }
+
+    /// Records the span id of the envelope being processed as the new parent.
+    #[inline]
+    pub fn set_parent_span(&self, span: SpanId) {
+        self.parent.set(Some(span));
+    }
+
+    /// Renders the current context as a W3C [`traceparent`] header value:
+    /// `00-{trace_id}-{span_id}-{flags}`.
+    ///
+    /// The `span_id` is the [`parent_span`](Self::parent_span) of this scope,
+    /// i.e. the id a remote peer should treat as the parent of its own spans.
+    /// If the scope is a root, a fresh span id is minted so the header is still
+    /// valid. The trace id (64-bit in elfo) is left-padded to the 128-bit
+    /// `trace-id` field.
+    ///
+    /// [`traceparent`]: https://www.w3.org/TR/trace-context/#traceparent-header
+    pub fn traceparent(&self) -> String {
+        let span = self.parent.get().unwrap_or_else(trace_id::generate);
+        format!(
+            "00-{:032x}-{:016x}-01",
+            u64::from(self.trace_id.get()),
+            u64::from(span),
+        )
+    }
+
     /// Returns the current permissions (for logging, telemetry and so on).
     #[inline]
     pub fn permissions(&self) -> Permissions {
@@ -115,6 +166,30 @@ impl Scope {
         &self.logging_limiter
     }
 
+    /// Returns the group's shutdown token.
+    ///
+    /// Sources and actors observe it to wind down on a coordinated stop.
+    #[inline]
+    pub fn shutdown_token(&self) -> &ShutdownToken {
+        &self.shutdown
+    }
+
+    /// Whether a coordinated shutdown is in progress for this group.
+    #[inline]
+    pub fn is_terminating(&self) -> bool {
+        self.shutdown.is_cancelled()
+    }
+
+    /// Resolves once the group's grace window elapses after a coordinated stop.
+    ///
+    /// Stays pending until the shutdown token is tripped, then sleeps for the
+    /// grace period set at cancellation; awaiting it lets an actor race its
+    /// in-flight work against the drain deadline before the runtime aborts it.
+    pub async fn terminated(&self) {
+        self.shutdown.cancelled().await;
+        tokio::time::sleep(self.shutdown.grace()).await;
+    }
+
     /// Wraps the provided future with the current scope.
     pub async fn within<F: Future>(self, f: F) -> F::Output {
         SCOPE.scope(self, f).await
@@ -181,6 +256,42 @@ pub fn set_trace_id(trace_id: TraceId) {
     with(|scope| scope.set_trace_id(trace_id));
 }
 
+/// Returns the span id of the envelope that installed the current scope.
+///
+/// # Panics
+/// This function will panic if called ouside the actor system.
+#[inline]
+pub fn parent_span() -> Option<SpanId> {
+    with(Scope::parent_span)
+}
+
+/// Records the span id of the envelope being processed as the new parent.
+///
+/// # Panics
+/// This function will panic if called ouside the actor system.
+#[inline]
+pub fn set_parent_span(span: SpanId) {
+    with(|scope| scope.set_parent_span(span));
+}
+
+/// Returns the current context as a W3C `traceparent` header value.
+///
+/// # Panics
+/// This function will panic if called ouside the actor system.
+#[inline]
+pub fn traceparent() -> String {
+    with(Scope::traceparent)
+}
+
+/// Whether a coordinated shutdown is in progress for the current group.
+///
+/// # Panics
+/// This function will panic if called ouside the actor system.
+#[inline]
+pub fn is_terminating() -> bool {
+    with(Scope::is_terminating)
+}
+
 /// Returns the current object's meta.
 ///
 /// # Panics
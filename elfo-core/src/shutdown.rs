@@ -0,0 +1,183 @@
+//! Coordinated shutdown primitives.
+//!
+//! A [`ShutdownToken`] is a cloneable cancellation signal plumbed through
+//! [`Scope`](crate::scope::Scope) alongside `permissions` and
+//! `logging_limiter`. The runtime trips it when a node is asked to stop; every
+//! actor in the affected groups then receives a [`Terminate`] system message
+//! carrying the grace window, and long-lived sources (e.g.
+//! [`Stream`](crate::stream::Stream)) observe the token and wind down instead
+//! of blocking the drain.
+
+use std::{
+    future::poll_fn,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    task::{self, Poll, Waker},
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+
+use elfo_macros::message;
+
+/// A system message asking a group to wind down within `grace`.
+///
+/// Delivered on [`ctx.recv()`](crate::context::Context::recv) once a
+/// coordinated shutdown begins (see [`ShutdownToken`]). Actors should finish
+/// their in-flight work and stop; when the grace window elapses the runtime
+/// aborts any task still running.
+#[message(elfo = crate)]
+#[non_exhaustive]
+pub struct Terminate {
+    /// How long the group has to drain before its tasks are aborted.
+    pub grace: Duration,
+}
+
+impl Terminate {
+    /// Creates a `Terminate` with the given grace window.
+    pub fn new(grace: Duration) -> Self {
+        Self { grace }
+    }
+}
+
+/// A cloneable cancellation signal shared by an actor group.
+///
+/// Cloning is cheap (an `Arc` bump) and all clones observe the same state, so a
+/// source can hold one and check [`is_cancelled`](Self::is_cancelled) on each
+/// poll while the supervisor holds another to trip it.
+#[derive(Clone)]
+pub struct ShutdownToken {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    cancelled: AtomicBool,
+    grace_nanos: AtomicU64,
+    // Wakers registered by `poll_cancelled` callers, one per task. The token
+    // is shared by a whole group, so several actors' sources and `terminated`
+    // futures can wait on it at once; unlike a fresh `Notify::notified()` per
+    // call, a registration survives across polls so a `cancel` that lands
+    // between polls still wakes the waiting task.
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl ShutdownToken {
+    /// Creates a fresh, untripped token.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                cancelled: AtomicBool::new(false),
+                grace_nanos: AtomicU64::new(0),
+                wakers: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Trips the token with the given grace period, waking every waiter.
+    ///
+    /// Idempotent: a second call keeps the first grace window.
+    pub fn cancel(&self, grace: Duration) {
+        if self.inner.cancelled.load(Ordering::SeqCst) {
+            return;
+        }
+
+        // `grace_nanos` must be visible before `cancelled` flips to `true`:
+        // otherwise a waiter woken between the two stores could observe
+        // `is_cancelled() == true` with `grace() == 0` and skip the grace
+        // sleep entirely (see `Scope::terminated`).
+        let nanos = u64::try_from(grace.as_nanos()).unwrap_or(u64::MAX);
+        self.inner.grace_nanos.store(nanos, Ordering::SeqCst);
+
+        if self.inner.cancelled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        for waker in std::mem::take(&mut *self.inner.wakers.lock()) {
+            waker.wake();
+        }
+    }
+
+    /// Whether the token has been tripped.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// The grace window set at cancellation (zero until then).
+    #[inline]
+    pub fn grace(&self) -> Duration {
+        Duration::from_nanos(self.inner.grace_nanos.load(Ordering::SeqCst))
+    }
+
+    /// Resolves once the token is tripped.
+    pub async fn cancelled(&self) {
+        poll_fn(|cx| self.poll_cancelled(cx)).await
+    }
+
+    /// Polls the token, returning `Ready` once it is tripped.
+    ///
+    /// Convenient for sources that drive their own `poll_recv`. Registers
+    /// `cx`'s waker before the second check, so a `cancel` racing with this
+    /// call can never be missed.
+    pub fn poll_cancelled(&self, cx: &mut task::Context<'_>) -> Poll<()> {
+        if self.is_cancelled() {
+            return Poll::Ready(());
+        }
+        {
+            let mut wakers = self.inner.wakers.lock();
+            if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+        }
+        if self.is_cancelled() {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+}
+
+impl Default for ShutdownToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_is_idempotent() {
+        let token = ShutdownToken::new();
+        token.cancel(Duration::from_secs(1));
+        token.cancel(Duration::from_secs(2));
+        assert_eq!(token.grace(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn grace_is_visible_once_cancelled() {
+        let token = ShutdownToken::new();
+        assert!(!token.is_cancelled());
+        assert_eq!(token.grace(), Duration::ZERO);
+
+        token.cancel(Duration::from_millis(500));
+        assert!(token.is_cancelled());
+        assert_eq!(token.grace(), Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn cancelled_resolves_after_cancel() {
+        let token = ShutdownToken::new();
+        let waiter = tokio::spawn({
+            let token = token.clone();
+            async move { token.cancelled().await }
+        });
+
+        // Give the waiter a chance to register its waker before cancelling.
+        tokio::task::yield_now().await;
+        token.cancel(Duration::from_millis(10));
+
+        waiter.await.expect("waiter panicked");
+    }
+}
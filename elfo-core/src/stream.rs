@@ -12,22 +12,49 @@ use crate::{
     context::Source,
     envelope::{Envelope, MessageKind},
     message::Message,
+    scope,
 };
 
-pub struct Stream<S>(Mutex<StreamState<S>>);
+pub struct Stream<S> {
+    state: Mutex<StreamState<S>>,
+    on_close: OnClose,
+}
 
 enum StreamState<S> {
     Active(Pin<Box<S>>),
     Closed,
 }
 
+/// What a [`Stream`] does once the underlying stream is exhausted or closed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OnClose {
+    /// Yield `Poll::Ready(None)` so the actor loop observes end-of-stream.
+    Finish,
+    /// Stay `Poll::Pending` forever, keeping the source alive but silent.
+    Linger,
+}
+
 impl<S> Stream<S> {
+    /// Creates a stream that stays `Poll::Pending` once exhausted, keeping the
+    /// source registered but silent.
     pub fn new(stream: S) -> Self {
-        Stream(Mutex::new(StreamState::Active(Box::pin(stream))))
+        Stream {
+            state: Mutex::new(StreamState::Active(Box::pin(stream))),
+            on_close: OnClose::Linger,
+        }
+    }
+
+    /// Creates a stream that yields `Poll::Ready(None)` once exhausted, so the
+    /// actor loop can observe end-of-stream.
+    pub fn finishing(stream: S) -> Self {
+        Stream {
+            state: Mutex::new(StreamState::Active(Box::pin(stream))),
+            on_close: OnClose::Finish,
+        }
     }
 
     pub fn set(&self, stream: S) {
-        *self.0.lock() = StreamState::Active(Box::pin(stream));
+        *self.state.lock() = StreamState::Active(Box::pin(stream));
     }
 
     pub fn replace(&self, stream: S) -> Option<S>
@@ -35,7 +62,7 @@ impl<S> Stream<S> {
         S: Unpin,
     {
         let new_state = StreamState::Active(Box::pin(stream));
-        match mem::replace(&mut *self.0.lock(), new_state) {
+        match mem::replace(&mut *self.state.lock(), new_state) {
             StreamState::Active(stream) => Some(*Pin::into_inner(stream)),
             StreamState::Closed => None,
         }
@@ -43,10 +70,112 @@ impl<S> Stream<S> {
 
     pub fn close(&self) -> bool {
         !matches!(
-            mem::replace(&mut *self.0.lock(), StreamState::Closed),
+            mem::replace(&mut *self.state.lock(), StreamState::Closed),
             StreamState::Closed
         )
     }
+
+    /// The poll result to use once the underlying stream is closed.
+    #[inline]
+    fn closed_poll(&self) -> Poll<Option<Envelope>> {
+        match self.on_close {
+            OnClose::Finish => Poll::Ready(None),
+            OnClose::Linger => Poll::Pending,
+        }
+    }
+
+    /// Transitions to `Closed` if the group's shutdown token has been tripped,
+    /// registering the waker so a later cancellation re-polls this source.
+    #[inline]
+    fn poll_shutdown(&self, cx: &mut task::Context<'_>, state: &mut StreamState<S>) -> bool {
+        let cancelled = scope::try_with(|scope| scope.shutdown_token().poll_cancelled(cx).is_ready())
+            .unwrap_or(false);
+
+        if cancelled {
+            *state = StreamState::Closed;
+        }
+        cancelled
+    }
+}
+
+impl<S, T, E> Stream<FromResults<S, T, E>>
+where
+    S: FutStream<Item = Result<T, E>>,
+    T: Message,
+{
+    /// Drives an actor from a fallible stream, staying `Poll::Pending` once it
+    /// is exhausted, keeping the source registered but silent (like
+    /// [`Stream::new`]).
+    ///
+    /// `Ok(msg)` is delivered as a regular envelope, while `Err(e)` is mapped
+    /// through `map_err` into a message the actor can `msg!`-match, so a
+    /// transport error becomes a routable event instead of a lost one or a
+    /// panic.
+    pub fn from_results<N, M>(stream: S, map_err: M) -> Self
+    where
+        N: Message,
+        M: FnMut(E) -> N + Send + 'static,
+    {
+        Self::from_results_with(stream, map_err, OnClose::Linger)
+    }
+
+    /// Like [`from_results`](Self::from_results) but yields `Poll::Ready(None)`
+    /// once exhausted, so the actor loop can observe end-of-stream (like
+    /// [`Stream::finishing`]).
+    pub fn from_results_finishing<N, M>(stream: S, map_err: M) -> Self
+    where
+        N: Message,
+        M: FnMut(E) -> N + Send + 'static,
+    {
+        Self::from_results_with(stream, map_err, OnClose::Finish)
+    }
+
+    fn from_results_with<N, M>(stream: S, mut map_err: M, on_close: OnClose) -> Self
+    where
+        N: Message,
+        M: FnMut(E) -> N + Send + 'static,
+    {
+        // The user returns a plain message; we wrap it the same way the `Ok`
+        // arm wraps its item, so both paths look identical to the actor loop.
+        let map_err = Box::new(move |err| {
+            let message = map_err(err);
+            Envelope::new(message, MessageKind::Regular { sender: Addr::NULL }).upcast()
+        });
+        Stream {
+            state: Mutex::new(StreamState::Active(Box::pin(FromResults { stream, map_err }))),
+            on_close,
+        }
+    }
+}
+
+/// Adapts a `Stream<Item = Result<T, E>>` into a stream of envelopes, turning
+/// errors into routable messages via a user-supplied closure.
+#[pin_project::pin_project]
+pub struct FromResults<S, T, E> {
+    #[pin]
+    stream: S,
+    map_err: Box<dyn FnMut(E) -> Envelope + Send>,
+}
+
+impl<S, T, E> FutStream for FromResults<S, T, E>
+where
+    S: FutStream<Item = Result<T, E>>,
+    T: Message,
+{
+    type Item = Envelope;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Envelope>> {
+        let this = self.project();
+        match this.stream.poll_next(cx) {
+            Poll::Ready(Some(Ok(message))) => {
+                let kind = MessageKind::Regular { sender: Addr::NULL };
+                Poll::Ready(Some(Envelope::new(message, kind).upcast()))
+            }
+            Poll::Ready(Some(Err(err))) => Poll::Ready(Some((this.map_err)(err))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
 }
 
 impl<S> Source for Stream<S>
@@ -55,11 +184,15 @@ where
     S::Item: Message,
 {
     fn poll_recv(&self, cx: &mut task::Context<'_>) -> Poll<Option<Envelope>> {
-        let mut state = self.0.lock();
+        let mut state = self.state.lock();
+
+        if self.poll_shutdown(cx, &mut state) {
+            return self.closed_poll();
+        }
 
         let stream = match &mut *state {
             StreamState::Active(stream) => stream,
-            StreamState::Closed => return Poll::Pending, // TODO: `Poll::Ready(None)`?
+            StreamState::Closed => return self.closed_poll(),
         };
 
         match stream.as_mut().poll_next(cx) {
@@ -70,9 +203,102 @@ where
             }
             Poll::Ready(None) => {
                 *state = StreamState::Closed;
-                Poll::Ready(None)
+                self.closed_poll()
             }
             Poll::Pending => Poll::Pending,
         }
     }
-}
\ No newline at end of file
+}
+
+impl<S, T, E> Source for Stream<FromResults<S, T, E>>
+where
+    S: FutStream<Item = Result<T, E>>,
+    T: Message,
+{
+    fn poll_recv(&self, cx: &mut task::Context<'_>) -> Poll<Option<Envelope>> {
+        let mut state = self.state.lock();
+
+        if self.poll_shutdown(cx, &mut state) {
+            return self.closed_poll();
+        }
+
+        let stream = match &mut *state {
+            StreamState::Active(stream) => stream,
+            StreamState::Closed => return self.closed_poll(),
+        };
+
+        match stream.as_mut().poll_next(cx) {
+            Poll::Ready(Some(envelope)) => Poll::Ready(Some(envelope)),
+            Poll::Ready(None) => {
+                *state = StreamState::Closed;
+                self.closed_poll()
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elfo_macros::message;
+    use futures::{stream, task::noop_waker};
+
+    use super::*;
+
+    #[message(elfo = crate)]
+    struct Ping(u32);
+
+    fn poll(source: &impl Source) -> Poll<Option<Envelope>> {
+        let waker = noop_waker();
+        let mut cx = task::Context::from_waker(&waker);
+        source.poll_recv(&mut cx)
+    }
+
+    #[test]
+    fn new_lingers_once_exhausted() {
+        let source = Stream::new(stream::iter([Ping(1)]));
+
+        assert!(matches!(poll(&source), Poll::Ready(Some(_))));
+        // Stays pending forever rather than reporting end-of-stream.
+        assert!(matches!(poll(&source), Poll::Pending));
+        assert!(matches!(poll(&source), Poll::Pending));
+    }
+
+    #[test]
+    fn finishing_reports_end_of_stream() {
+        let source = Stream::finishing(stream::iter([Ping(1)]));
+
+        assert!(matches!(poll(&source), Poll::Ready(Some(_))));
+        assert!(matches!(poll(&source), Poll::Ready(None)));
+        // Stays closed rather than panicking or yielding stale items.
+        assert!(matches!(poll(&source), Poll::Ready(None)));
+    }
+
+    #[test]
+    fn close_short_circuits_future_polls() {
+        let source = Stream::finishing(stream::iter([Ping(1)]));
+
+        assert!(source.close());
+        assert!(!source.close(), "closing twice reports no state change");
+        assert!(matches!(poll(&source), Poll::Ready(None)));
+    }
+
+    #[test]
+    fn from_results_finishing_maps_err_and_then_ends() {
+        let results: Vec<Result<Ping, &'static str>> = vec![Ok(Ping(1)), Err("boom")];
+        let source = Stream::from_results_finishing(stream::iter(results), |_err| Ping(0));
+
+        assert!(matches!(poll(&source), Poll::Ready(Some(_))));
+        assert!(matches!(poll(&source), Poll::Ready(Some(_)))); // the mapped error
+        assert!(matches!(poll(&source), Poll::Ready(None)));
+    }
+
+    #[test]
+    fn from_results_lingers_by_default() {
+        let results: Vec<Result<Ping, &'static str>> = vec![Ok(Ping(1))];
+        let source = Stream::from_results(stream::iter(results), |_err| Ping(0));
+
+        assert!(matches!(poll(&source), Poll::Ready(Some(_))));
+        assert!(matches!(poll(&source), Poll::Pending));
+    }
+}
@@ -0,0 +1,127 @@
+use std::{future::Future, panic::AssertUnwindSafe, sync::Arc, time::Duration};
+
+use futures::FutureExt;
+use parking_lot::Mutex;
+use tokio::task::JoinHandle;
+use tracing::error;
+
+use crate::{
+    addr::Addr,
+    dataspace::{Dataspace, Retracted},
+    envelope::{Envelope, MessageKind},
+    object::AddressBook,
+    shutdown::{ShutdownToken, Terminate},
+};
+
+/// Owns a group's actors and the machinery that stops them cleanly.
+pub(crate) struct Supervisor {
+    group: Addr,
+    book: AddressBook,
+    shutdown: ShutdownToken,
+    actors: Mutex<Vec<(Addr, tokio::task::AbortHandle)>>,
+    dataspace: Arc<Mutex<Dataspace>>,
+}
+
+impl Supervisor {
+    /// Creates a supervisor for `group`, sharing the book and dataspace every
+    /// actor spawned into it routes through and asserts into.
+    pub(crate) fn new(
+        group: Addr,
+        book: AddressBook,
+        shutdown: ShutdownToken,
+        dataspace: Arc<Mutex<Dataspace>>,
+    ) -> Self {
+        Self {
+            group,
+            book,
+            shutdown,
+            actors: Mutex::new(Vec::new()),
+            dataspace,
+        }
+    }
+
+    /// Spawns an actor's body under this group's supervision.
+    ///
+    /// Registers `actor` so a later [`terminate`](Self::terminate) reaches it
+    /// and can abort it once its grace window elapses, and — whether `fut`
+    /// returns normally or panics — runs
+    /// [`on_actor_dropped`](Self::on_actor_dropped) once it completes, which is
+    /// what makes dataspace retraction automatic rather than something each
+    /// actor body has to remember to do.
+    pub(crate) fn spawn(
+        self: &Arc<Self>,
+        actor: Addr,
+        fut: impl Future<Output = ()> + Send + 'static,
+    ) -> JoinHandle<()> {
+        let this = self.clone();
+        let handle = tokio::spawn(async move {
+            // A panicking actor must not take the dataspace's consistency
+            // with it, so the invariant in `on_actor_dropped` runs either way.
+            if let Err(panic) = AssertUnwindSafe(fut).catch_unwind().await {
+                error!(%actor, payload = panic_message(&panic), "actor panicked");
+            }
+
+            this.actors.lock().retain(|(a, _)| *a != actor);
+            this.on_actor_dropped(actor);
+        });
+
+        self.actors.lock().push((actor, handle.abort_handle()));
+        handle
+    }
+
+    /// Begins a coordinated shutdown of the group.
+    ///
+    /// Trips the shared [`ShutdownToken`] so long-lived sources (e.g.
+    /// [`Stream`](crate::stream::Stream)) observe cancellation and wind down,
+    /// then delivers a [`Terminate`] system message carrying `grace` to every
+    /// live actor so they can finish in-flight requests before the window
+    /// elapses. Actors that ignore `is_terminating()`/`terminated()` and are
+    /// still running once `grace` elapses are aborted.
+    pub(crate) fn terminate(&self, grace: Duration) {
+        self.shutdown.cancel(grace);
+
+        let actors = self.actors.lock().clone();
+        for &(actor, _) in &actors {
+            let envelope =
+                Envelope::new(Terminate::new(grace), MessageKind::Regular { sender: Addr::NULL });
+            self.book.try_send(actor, envelope.upcast());
+        }
+
+        tokio::spawn(async move {
+            tokio::time::sleep(grace).await;
+            for (_, handle) in actors {
+                handle.abort();
+            }
+        });
+    }
+
+    /// Retracts every fact an actor asserted once its object is dropped.
+    ///
+    /// This is the automatic-retraction invariant: whether the actor exited
+    /// normally or panicked, its standing facts are withdrawn and every
+    /// matching observer is delivered a [`Retracted`], so the dataspace stays
+    /// consistent without manual cleanup.
+    pub(crate) fn on_actor_dropped(&self, actor: Addr) {
+        let retracted = self.dataspace.lock().retract_all(actor);
+
+        for (handle, observers) in retracted {
+            for observer in observers {
+                let envelope =
+                    Envelope::new(Retracted { handle }, MessageKind::Regular { sender: Addr::NULL });
+                self.book.try_send(observer, envelope.upcast());
+            }
+        }
+    }
+}
+
+/// Extracts a human-readable message from a `catch_unwind` payload, falling
+/// back to a placeholder for panics that didn't unwind with a `&str`/`String`.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "<non-string panic payload>"
+    }
+}
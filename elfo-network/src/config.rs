@@ -0,0 +1,23 @@
+use std::net::SocketAddr;
+
+use serde::Deserialize;
+
+/// The network transport configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    /// This node's id, exchanged in the handshake on connect so peers can
+    /// address groups back across the transport.
+    pub node: u64,
+    /// The address to accept inbound connections on.
+    pub listen: SocketAddr,
+    /// Peers to dial on startup and keep connected.
+    #[serde(default)]
+    pub peers: Vec<SocketAddr>,
+    /// Maximum frame size in bytes; larger envelopes are rejected.
+    #[serde(default = "default_max_frame_size")]
+    pub max_frame_size: usize,
+}
+
+fn default_max_frame_size() -> usize {
+    16 * 1024 * 1024
+}
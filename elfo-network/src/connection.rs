@@ -0,0 +1,558 @@
+use std::{io, net::SocketAddr, sync::Arc, time::Duration};
+
+use fxhash::FxHashMap;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    time::sleep,
+};
+use tokio_tungstenite::{
+    accept_async_with_config, client_async_with_config,
+    tungstenite::{protocol::WebSocketConfig, Message as Ws},
+};
+use tracing::{error, info, warn};
+
+use elfo_core as elfo;
+use elfo_macros::{message, msg_raw as msg};
+
+use elfo::{
+    errors::{RequestError, SendError},
+    messages::ConfigUpdated,
+    scope, Addr, ActorGroup, AnyMessage, Context, Local, Message, ResponseToken, Schema,
+};
+
+use crate::{
+    config::Config,
+    frame::{Frame, FrameKind, RemoteAddr},
+    routing::RoutingTable,
+};
+
+/// A framed chunk read off a connection, re-injected into the routing layer.
+#[message(elfo = elfo_core)]
+struct FrameReceived {
+    from: u64,
+    frame: Arc<Local<Frame>>,
+}
+
+/// A connection came up: the reader/writer tasks register their outbound
+/// channel and announce the remote group so it can be bound.
+#[message(elfo = elfo_core)]
+struct ConnectionUp {
+    node: u64,
+    group: Addr,
+    outbound: Arc<Local<mpsc::UnboundedSender<Frame>>>,
+}
+
+/// The underlying transport dropped; bindings for `node` are forgotten and the
+/// peer is re-dialed. Routable rather than fatal, mirroring `ServerFailed`.
+#[message(elfo = elfo_core)]
+struct ConnectionLost {
+    node: u64,
+    error: Arc<Local<io::Error>>,
+}
+
+/// A one-way message a proxy intercepted for a remote group; serialized and
+/// shipped as a `Regular` frame. `recipient` is the local proxy address, which
+/// the routing table resolves to the remote one.
+#[message(elfo = elfo_core)]
+struct TunnelSend {
+    recipient: Addr,
+    message: Arc<Local<AnyMessage>>,
+}
+
+/// A request a proxy intercepted for a remote group. The response is tunneled
+/// back over the connection and resolved onto the originating `token`, so
+/// `ctx.request(Req).resolve()` works transparently across the hop.
+#[message(ret = AnyMessage, elfo = elfo_core)]
+struct Tunnel {
+    recipient: Addr,
+    message: Arc<Local<AnyMessage>>,
+}
+
+/// Asserted into the dataspace once a remote group is bound, so any local
+/// actor can `ctx.observe::<RemoteBound>()` to discover it and build a
+/// [`RemoteHandle`] instead of having to know the proxy address up front.
+#[message(elfo = elfo_core)]
+pub struct RemoteBound {
+    /// The local proxy address this transport listens on for tunneled
+    /// traffic to the newly bound group.
+    pub proxy: Addr,
+}
+
+/// A handle to a remote group, built from a [`RemoteBound`] fact.
+///
+/// Wraps the local proxy [`Addr`] this transport listens on, so reaching the
+/// remote group only costs constructing and sending a [`TunnelSend`]/[`Tunnel`]
+/// to it — the transport on the other end of the `ctx.send`/`ctx.request` call
+/// does the actual framing and hop.
+#[derive(Clone, Copy)]
+pub struct RemoteHandle {
+    proxy: Addr,
+}
+
+impl From<RemoteBound> for RemoteHandle {
+    fn from(bound: RemoteBound) -> Self {
+        Self { proxy: bound.proxy }
+    }
+}
+
+impl RemoteHandle {
+    /// Ships a one-way message to the remote group.
+    pub async fn send<C: 'static, M: Message>(
+        &self,
+        ctx: &Context<C>,
+        message: M,
+    ) -> Result<(), SendError<TunnelSend>> {
+        ctx.send_to(
+            self.proxy,
+            TunnelSend {
+                recipient: self.proxy,
+                message: Arc::new(Local::from(message.upcast())),
+            },
+        )
+        .await
+    }
+
+    /// Sends a request to the remote group and awaits its single response.
+    pub async fn request<C: 'static, M: Message>(
+        &self,
+        ctx: &Context<C>,
+        message: M,
+    ) -> Result<AnyMessage, RequestError> {
+        ctx.request_to(
+            self.proxy,
+            Tunnel {
+                recipient: self.proxy,
+                message: Arc::new(Local::from(message.upcast())),
+            }
+            .upcast(),
+        )
+        .await
+    }
+}
+
+/// The handshake each side sends once on connect so peers learn one another's
+/// node id and the group reachable over the link.
+#[derive(Clone, Serialize, Deserialize)]
+struct Hello {
+    node: u64,
+    group: Addr,
+}
+
+struct Transport {
+    ctx: Context<Config>,
+    routing: RoutingTable,
+    conns: FxHashMap<u64, mpsc::UnboundedSender<Frame>>,
+    pending: FxHashMap<u64, (u64, ResponseToken)>,
+    next_request_id: u64,
+}
+
+pub(crate) fn new() -> Schema {
+    ActorGroup::new()
+        .config::<Config>()
+        .exec(move |ctx| Transport::new(ctx).main())
+}
+
+impl Transport {
+    fn new(ctx: Context<Config>) -> Self {
+        Self {
+            ctx,
+            routing: RoutingTable::default(),
+            conns: FxHashMap::default(),
+            pending: FxHashMap::default(),
+            next_request_id: 0,
+        }
+    }
+
+    async fn main(mut self) {
+        self.listen(self.ctx.config().listen);
+        for peer in self.ctx.config().peers.clone() {
+            self.dial(peer);
+        }
+
+        while let Some(envelope) = self.ctx.recv().await {
+            msg!(match envelope {
+                ConfigUpdated => {
+                    info!("config updated, reconciling peers");
+                    for peer in self.ctx.config().peers.clone() {
+                        self.dial(peer);
+                    }
+                }
+                ConnectionUp { node, group, outbound } => {
+                    self.conns.insert(node, (**outbound).clone());
+                    self.bind_remote(node, group);
+                }
+                FrameReceived { from, frame } => {
+                    self.deliver(from, &frame);
+                }
+                ConnectionLost { node, error } => {
+                    warn!(node, error = %&**error, "connection lost, re-dialing");
+                    self.conns.remove(&node);
+                    self.routing.forget_node(node);
+                    // Dropping a parked token fails its request at once.
+                    self.pending.retain(|_, (pending_node, _)| *pending_node != node);
+                }
+                TunnelSend { recipient, message } => {
+                    self.send_regular(*recipient, &message);
+                }
+                (Tunnel { recipient, message }, token) => {
+                    self.send_request(*recipient, &message, token);
+                }
+            });
+        }
+    }
+
+    /// Binds a newly discovered remote group so local sends reach it.
+    ///
+    /// The transport itself is the proxy: its own (real, locally meaningful)
+    /// address is recorded in the [`RoutingTable`] both ways — a
+    /// [`RemoteHandle`] built from the [`RemoteBound`] fact asserted below
+    /// resolves to the [`RemoteAddr`] and is tunneled, and an inbound frame
+    /// naming that `RemoteAddr` resolves back to this transport's address for
+    /// delivery. `group` is the address the *peer* uses for itself and is only
+    /// meaningful when paired with `node` — it must never be used as a local
+    /// routing key.
+    fn bind_remote(&mut self, node: u64, group: Addr) {
+        let proxy = self.ctx.addr();
+        let remote = RemoteAddr { node, local: group };
+        self.routing.bind(proxy, remote);
+        self.ctx.assert(RemoteBound { proxy });
+        info!(node, ?group, "bound a remote group");
+    }
+
+    /// Re-injects an inbound frame into the local routing layer, resolving the
+    /// tunneled response token for a `Response` or delivering a fresh envelope
+    /// otherwise. The frame's `traceparent` seeds the scope so the distributed
+    /// trace stays connected.
+    fn deliver(&mut self, from: u64, frame: &Frame) {
+        match frame.kind {
+            FrameKind::Regular => self.inject(frame, None),
+            FrameKind::Request { request_id } => self.inject(frame, Some((from, request_id))),
+            FrameKind::Response { request_id } => self.resolve(request_id, frame),
+        }
+    }
+
+    /// Deserializes a frame's payload and routes it to the bound local actor.
+    fn inject(&self, frame: &Frame, reply: Option<(u64, u64)>) {
+        let Some(local) = self.routing.to_local(frame.recipient) else {
+            warn!(?frame.recipient, "no local binding for inbound frame, dropping");
+            return;
+        };
+
+        let Some(message) = codec::decode(&frame.protocol, &frame.name, &frame.payload) else {
+            error!(protocol = %frame.protocol, name = %frame.name, "cannot decode an inbound frame");
+            return;
+        };
+
+        seed_scope(&frame.traceparent);
+
+        match reply {
+            None => {
+                let ctx = self.ctx.pruned();
+                let scope = scope::expose();
+
+                tokio::spawn(scope.within(async move {
+                    let _ = ctx.send_to(local, message).await;
+                }));
+            }
+            Some((from, request_id)) => {
+                let ctx = self.ctx.pruned();
+                let outbound = self.conns.get(&from).cloned();
+                let scope = scope::expose();
+
+                tokio::spawn(scope.within(async move {
+                    let Ok(response) = ctx.request_to(local, message).await else {
+                        return;
+                    };
+                    if let Some(tx) = outbound {
+                        let frame = encode(
+                            RemoteAddr { node: from, local },
+                            FrameKind::Response { request_id },
+                            &response,
+                        );
+                        let _ = tx.send(frame);
+                    }
+                }));
+            }
+        }
+    }
+
+    /// Resolves a previously tunneled request: pops its pending token and
+    /// completes it with the deserialized response, so the originating
+    /// `resolve().await` returns.
+    fn resolve(&mut self, request_id: u64, frame: &Frame) {
+        let Some((_, token)) = self.pending.remove(&request_id) else {
+            warn!(request_id, "response for an unknown request, dropping");
+            return;
+        };
+
+        match codec::decode(&frame.protocol, &frame.name, &frame.payload) {
+            Some(response) => self.ctx.respond(token, response),
+            None => error!("cannot decode a tunneled response"),
+        }
+    }
+
+    /// Ships a one-way message to the remote group a proxy stands for.
+    fn send_regular(&self, recipient: Addr, message: &AnyMessage) {
+        let Some(remote) = self.routing.to_remote(recipient) else {
+            warn!(?recipient, "no remote binding for a send, dropping");
+            return;
+        };
+        let Some(tx) = self.conns.get(&remote.node) else {
+            warn!(node = remote.node, "no connection for a remote send, dropping");
+            return;
+        };
+        let _ = tx.send(encode(remote, FrameKind::Regular, message));
+    }
+
+    /// Ships a request to the remote group a proxy stands for and parks its
+    /// `token` until the response frame comes back (see
+    /// [`resolve`](Self::resolve)).
+    fn send_request(
+        &mut self,
+        recipient: Addr,
+        message: &AnyMessage,
+        token: ResponseToken,
+    ) {
+        let Some(remote) = self.routing.to_remote(recipient) else {
+            warn!(?recipient, "no remote binding for a request, dropping the token");
+            return;
+        };
+        let Some(tx) = self.conns.get(&remote.node).cloned() else {
+            warn!(node = remote.node, "no connection for a remote request, dropping the token");
+            return;
+        };
+
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1);
+        self.pending.insert(request_id, (remote.node, token));
+
+        let _ = tx.send(encode(remote, FrameKind::Request { request_id }, message));
+    }
+
+    fn listen(&self, addr: SocketAddr) {
+        let ctx = self.ctx.pruned();
+        let hello = self.hello();
+        let max_frame_size = self.ctx.config().max_frame_size;
+
+        tokio::spawn(async move {
+            let listener = match TcpListener::bind(addr).await {
+                Ok(listener) => listener,
+                Err(err) => {
+                    error!(%addr, %err, "failed to bind the transport listener");
+                    return;
+                }
+            };
+            info!(%addr, "listening for peers");
+
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let ctx = ctx.pruned();
+                        let hello = hello.clone();
+                        tokio::spawn(async move {
+                            let config = websocket_config(max_frame_size);
+                            match accept_async_with_config(stream, Some(config)).await {
+                                Ok(ws) => run_connection(ctx, hello, ws).await,
+                                Err(err) => warn!(%err, "websocket handshake failed"),
+                            }
+                        });
+                    }
+                    Err(err) => warn!(%err, "failed to accept a connection"),
+                }
+            }
+        });
+    }
+
+    fn dial(&self, peer: SocketAddr) {
+        let ctx = self.ctx.pruned();
+        let hello = self.hello();
+        let max_frame_size = self.ctx.config().max_frame_size;
+
+        tokio::spawn(async move {
+            let mut backoff = Duration::from_millis(100);
+            loop {
+                match connect(peer, max_frame_size).await {
+                    Ok(ws) => {
+                        run_connection(ctx.pruned(), hello.clone(), ws).await;
+                        backoff = Duration::from_millis(100);
+                    }
+                    Err(err) => warn!(%peer, %err, "failed to dial a peer, retrying"),
+                }
+                sleep(backoff).await;
+                backoff = (backoff * 2).min(Duration::from_secs(5));
+            }
+        });
+    }
+
+    fn hello(&self) -> Hello {
+        Hello {
+            node: self.ctx.config().node,
+            group: self.ctx.group(),
+        }
+    }
+}
+
+/// Serializes an outbound message into a [`Frame`] stamped with the current
+/// scope's `traceparent` and the message's protocol/name for registry lookup
+/// on the far side.
+fn encode(recipient: RemoteAddr, kind: FrameKind, message: &AnyMessage) -> Frame {
+    Frame {
+        recipient,
+        kind,
+        traceparent: scope::traceparent(),
+        protocol: message.protocol().to_string(),
+        name: message.name().to_string(),
+        payload: codec::encode(message),
+    }
+}
+
+/// Opens a websocket connection to `peer`, rejecting frames over
+/// `max_frame_size` just like the listening side.
+async fn connect(peer: SocketAddr, max_frame_size: usize) -> io::Result<WebSocket<TcpStream>> {
+    let stream = TcpStream::connect(peer).await?;
+    let url = format!("ws://{peer}/");
+    let config = websocket_config(max_frame_size);
+    let (ws, _) = client_async_with_config(url, stream, Some(config))
+        .await
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    Ok(ws)
+}
+
+/// Builds the websocket config enforcing [`Config::max_frame_size`](crate::config::Config::max_frame_size).
+fn websocket_config(max_frame_size: usize) -> WebSocketConfig {
+    WebSocketConfig {
+        max_frame_size: Some(max_frame_size),
+        ..Default::default()
+    }
+}
+
+type WebSocket<S> = tokio_tungstenite::WebSocketStream<S>;
+
+/// Runs a single connection to completion: exchanges [`Hello`]s, pumps outbound
+/// frames from a channel to the socket, and feeds inbound frames back to the
+/// actor as [`FrameReceived`]. Once the handshake is done, a transport error is
+/// reported as [`ConnectionLost`] rather than propagated, so a flaky peer never
+/// takes the node down; a failed handshake is only logged.
+async fn run_connection<S>(ctx: Context<Config>, hello: Hello, ws: WebSocket<S>)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    let (mut sink, mut source) = ws.split();
+
+    if sink
+        .send(Ws::Binary(rmp_serde::to_vec(&hello).expect("hello")))
+        .await
+        .is_err()
+    {
+        return;
+    }
+    let remote = match source.next().await {
+        Some(Ok(Ws::Binary(bytes))) => match rmp_serde::from_slice::<Hello>(&bytes) {
+            Ok(remote) => remote,
+            Err(err) => return warn!(%err, "malformed handshake from a peer"),
+        },
+        _ => return warn!("a peer closed before the handshake"),
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<Frame>();
+    let _ = ctx
+        .send(ConnectionUp {
+            node: remote.node,
+            group: remote.group,
+            outbound: Arc::new(Local::from(tx)),
+        })
+        .await;
+
+    let writer = tokio::spawn(async move {
+        while let Some(frame) = rx.recv().await {
+            let bytes = match rmp_serde::to_vec(&frame) {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    error!(%err, "failed to serialize an outbound frame");
+                    continue;
+                }
+            };
+            if sink.send(Ws::Binary(bytes)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let error = loop {
+        match source.next().await {
+            Some(Ok(Ws::Binary(bytes))) => match rmp_serde::from_slice::<Frame>(&bytes) {
+                Ok(frame) => {
+                    let _ = ctx
+                        .send(FrameReceived {
+                            from: remote.node,
+                            frame: Arc::new(Local::from(frame)),
+                        })
+                        .await;
+                }
+                Err(err) => warn!(%err, "discarding a malformed frame"),
+            },
+            Some(Ok(Ws::Close(_))) | None => {
+                break io::Error::new(io::ErrorKind::UnexpectedEof, "peer closed the connection");
+            }
+            Some(Ok(_)) => {}
+            Some(Err(err)) => break io::Error::new(io::ErrorKind::Other, err),
+        }
+    };
+
+    writer.abort();
+    let _ = ctx
+        .send(ConnectionLost {
+            node: remote.node,
+            error: Arc::new(Local::from(error)),
+        })
+        .await;
+}
+
+/// Inherits a frame's `traceparent` into the current scope so the envelope we
+/// re-inject continues the distributed trace instead of starting a fresh one,
+/// linked to the sender's span rather than rooting a new chain locally.
+fn seed_scope(traceparent: &str) {
+    // `00-{trace_id}-{span_id}-{flags}`; take the low 64 bits of the 128-bit
+    // trace-id field to match elfo's representation (span-id is 64-bit).
+    let mut parts = traceparent.split('-');
+    let Some(_version) = parts.next() else { return };
+    let Some(trace) = parts.next() else { return };
+    let span = parts.next();
+
+    let low = &trace[trace.len().saturating_sub(16)..];
+    if let Ok(raw) = u64::from_str_radix(low, 16) {
+        if let Ok(trace_id) = elfo::trace_id::TraceId::try_from(raw) {
+            scope::set_trace_id(trace_id);
+        }
+    }
+
+    if let Some(span) = span {
+        if let Ok(raw) = u64::from_str_radix(span, 16) {
+            if let Ok(span_id) = scope::SpanId::try_from(raw) {
+                scope::set_parent_span(span_id);
+            }
+        }
+    }
+}
+
+/// Wire (de)serialization of messages through the group's protocol registry.
+///
+/// A message is serialized with its `(protocol, name)` so the receiver can look
+/// up the matching deserializer and reconstruct a typed [`AnyMessage`] — a
+/// blind `rmp_serde::from_slice::<AnyMessage>` cannot, since `AnyMessage` is
+/// type-erased and carries no type tag of its own.
+mod codec {
+    use elfo_core::{AnyMessage, _priv::MessageRegistry};
+
+    pub(super) fn encode(message: &AnyMessage) -> Vec<u8> {
+        message.write_msgpack().expect("a message failed to serialize")
+    }
+
+    pub(super) fn decode(protocol: &str, name: &str, payload: &[u8]) -> Option<AnyMessage> {
+        let vtable = MessageRegistry::get(protocol, name)?;
+        (vtable.read_msgpack)(payload).ok()
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use elfo_core::Addr;
+
+/// The envelope representation that travels across a connection.
+///
+/// A frame is self-describing enough for the remote node to re-inject the
+/// message into its routing layer and to tunnel a response back: it carries
+/// the logical routing target, the kind (so requests can be answered), and the
+/// W3C `traceparent` of the originating scope so distributed traces stay
+/// joined.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Frame {
+    /// Routing target on the remote node.
+    pub recipient: RemoteAddr,
+    /// Whether this frame expects a response, and how to correlate it.
+    pub kind: FrameKind,
+    /// The `00-{trace_id}-{span_id}-{flags}` context of the sender's scope.
+    pub traceparent: String,
+    /// The message's protocol, used with `name` to look up its deserializer in
+    /// the receiver's message registry.
+    pub protocol: String,
+    /// The message's name within `protocol`.
+    pub name: String,
+    /// The `serde`-serialized message payload.
+    pub payload: Vec<u8>,
+}
+
+/// How a frame should be delivered once it reaches the remote node.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum FrameKind {
+    /// A one-way message.
+    Regular,
+    /// A request; the response must be tunneled back under `request_id`.
+    Request { request_id: u64 },
+    /// A response to a previously tunneled request.
+    Response { request_id: u64 },
+}
+
+/// A node-qualified actor address.
+///
+/// Local [`Addr`]s are meaningful only within a process, so the wire form pairs
+/// the originating node id with the local address; the receiving routing layer
+/// translates it into a local handle for the corresponding proxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RemoteAddr {
+    pub node: u64,
+    pub local: Addr,
+}
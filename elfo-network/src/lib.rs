@@ -0,0 +1,42 @@
+//! Network transport for cross-node actor groups.
+//!
+//! Locally `Addr` and `ctx.send`/`ctx.request` resolve within a single
+//! process. This crate lets an [`ActorGroup`] on one node exchange messages
+//! with groups on another: a [`connection`] actor serializes envelopes (every
+//! message already derives `serde` via `#[message]`) and ships them over a
+//! framed stream, while the [`routing`] layer tracks which local proxy address
+//! stands for which remote one.
+//!
+//! A remote group is discovered, not configured up front: once this transport
+//! binds one, it asserts a [`RemoteBound`] fact into the dataspace. A local
+//! actor observes it (`ctx.observe::<RemoteBound>()`) and builds a
+//! [`RemoteHandle`] from the fact, then reaches the remote group with
+//! [`RemoteHandle::send`]/[`RemoteHandle::request`] — the closest this
+//! transport gets to a bare `ctx.send`/`ctx.request`, short of the remote
+//! group's address being registered in the local address book itself.
+//!
+//! Requests survive the hop: the response [`token`](elfo_core::ResponseToken)
+//! is tunneled back over the connection and resolved on the originating node.
+//! Each frame also carries the `traceparent`/trace-id context (see
+//! [`elfo_core::scope::traceparent`]) so distributed traces stay connected.
+
+#![warn(rust_2018_idioms, unreachable_pub)]
+
+use elfo_core::Schema;
+
+mod config;
+mod connection;
+mod frame;
+mod routing;
+
+pub use crate::config::Config;
+pub use crate::connection::{RemoteBound, RemoteHandle};
+
+/// Builds the network subsystem's actor group.
+///
+/// Add it to a topology to accept inbound connections and dial the peers
+/// listed in [`Config`]; discovered remote groups then become reachable via
+/// [`RemoteHandle`].
+pub fn new() -> Schema {
+    connection::new()
+}
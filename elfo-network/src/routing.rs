@@ -0,0 +1,86 @@
+use fxhash::FxHashMap;
+
+use elfo_core::Addr;
+
+use crate::frame::RemoteAddr;
+
+/// Translates between local proxy addresses and remote node-qualified ones.
+///
+/// When the transport binds a remote group, it records its own address as the
+/// local *proxy* standing in for that group here. `ctx.send` to
+/// the proxy's [`Addr`] is then intercepted, serialized into a [`Frame`], and
+/// written to the owning connection; inbound frames are looked up in reverse to
+/// find the local delivery target.
+///
+/// [`Frame`]: crate::frame::Frame
+#[derive(Default)]
+pub(crate) struct RoutingTable {
+    to_remote: FxHashMap<Addr, RemoteAddr>,
+    to_local: FxHashMap<RemoteAddr, Addr>,
+}
+
+impl RoutingTable {
+    /// Binds a local proxy address to the remote address it represents.
+    pub(crate) fn bind(&mut self, local: Addr, remote: RemoteAddr) {
+        self.to_remote.insert(local, remote);
+        self.to_local.insert(remote, local);
+    }
+
+    /// Resolves a local proxy address to its remote counterpart.
+    pub(crate) fn to_remote(&self, local: Addr) -> Option<RemoteAddr> {
+        self.to_remote.get(&local).copied()
+    }
+
+    /// Resolves an inbound remote address to the local delivery target.
+    pub(crate) fn to_local(&self, remote: RemoteAddr) -> Option<Addr> {
+        self.to_local.get(&remote).copied()
+    }
+
+    /// Drops every binding for a remote node once its connection is lost, so
+    /// in-flight requests to it fail fast instead of hanging.
+    pub(crate) fn forget_node(&mut self, node: u64) {
+        self.to_local.retain(|remote, local| {
+            let keep = remote.node != node;
+            if !keep {
+                self.to_remote.remove(local);
+            }
+            keep
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bind_resolves_both_ways() {
+        let mut table = RoutingTable::default();
+        let remote = RemoteAddr { node: 1, local: Addr::NULL };
+
+        table.bind(Addr::NULL, remote);
+
+        assert_eq!(table.to_remote(Addr::NULL), Some(remote));
+        assert_eq!(table.to_local(remote), Some(Addr::NULL));
+    }
+
+    #[test]
+    fn forget_node_drops_only_that_nodes_bindings() {
+        let mut table = RoutingTable::default();
+        let kept = RemoteAddr { node: 2, local: Addr::NULL };
+        table.bind(Addr::NULL, kept);
+        table.bind(Addr::NULL, RemoteAddr { node: 1, local: Addr::NULL });
+
+        table.forget_node(1);
+
+        assert_eq!(table.to_remote(Addr::NULL), None);
+        assert_eq!(table.to_local(kept), Some(Addr::NULL));
+    }
+
+    #[test]
+    fn unknown_addresses_resolve_to_none() {
+        let table = RoutingTable::default();
+        assert_eq!(table.to_remote(Addr::NULL), None);
+        assert_eq!(table.to_local(RemoteAddr { node: 0, local: Addr::NULL }), None);
+    }
+}
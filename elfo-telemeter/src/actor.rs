@@ -10,7 +10,11 @@ use elfo::{
     messages::ConfigUpdated, scope, time::Interval, trace_id, ActorGroup, Context, Local, Schema,
 };
 
-use crate::{config::Config, render::Renderer, storage::Storage};
+use crate::{
+    config::{Config, Format},
+    render::Renderer,
+    storage::Storage,
+};
 
 struct Telemeter {
     ctx: Context<Config>,
@@ -18,15 +22,26 @@ struct Telemeter {
     renderer: Renderer,
 }
 
-#[message(ret = String, elfo = elfo_core)]
+#[message(ret = Rendered, elfo = elfo_core)]
 struct Render;
 
+/// A rendered snapshot plus the MIME type its format should be served with, so
+/// the scrape response never mislabels the body.
+#[message(elfo = elfo_core)]
+struct Rendered {
+    body: String,
+    content_type: String,
+}
+
 #[message(elfo = elfo_core)]
 struct CompactionTick;
 
 #[message(elfo = elfo_core)]
 struct ServerFailed(Arc<Local<hyper::Error>>);
 
+#[message(elfo = elfo_core)]
+struct PushFailed(Arc<Local<hyper::Error>>);
+
 pub(crate) fn new(storage: Arc<Storage>) -> Schema {
     ActorGroup::new()
         .config::<Config>()
@@ -45,6 +60,25 @@ impl Telemeter {
         }
     }
 
+    /// Renders the current snapshot in the configured exposition format, paired
+    /// with the MIME type it must be served with.
+    fn render(&self) -> Rendered {
+        let snapshot = self.storage.snapshot();
+        let descriptions = self.storage.descriptions();
+        let prometheus = self.renderer.render(snapshot, &descriptions);
+
+        match self.ctx.config().format {
+            Format::Prometheus => Rendered {
+                body: prometheus,
+                content_type: "text/plain; version=0.0.4; charset=utf-8".into(),
+            },
+            Format::OpenMetrics => Rendered {
+                body: to_openmetrics(&prometheus),
+                content_type: "application/openmetrics-text; version=1.0.0; charset=utf-8".into(),
+            },
+        }
+    }
+
     async fn main(mut self) {
         let interval = Interval::new(|| CompactionTick);
         let mut ctx = self.ctx.clone().with(&interval);
@@ -72,18 +106,28 @@ impl Telemeter {
                     // Rendering includes compaction, skip extra compaction tick.
                     interval.reset();
 
-                    let snapshot = self.storage.snapshot();
-                    let descriptions = self.storage.descriptions();
-                    let output = self.renderer.render(snapshot, &descriptions);
-                    ctx.respond(token, output);
+                    ctx.respond(token, self.render());
                 }
                 CompactionTick => {
                     self.storage.compact();
+
+                    // In push mode, ship the same snapshot we would have served
+                    // on scrape to the configured endpoint on each tick.
+                    if let Some(endpoint) = ctx.config().push_endpoint.clone() {
+                        let rendered = self.render();
+                        push(&self.ctx, endpoint, rendered);
+                    }
                 }
                 ServerFailed(error) => {
                     error!(error = %&**error, "server failed");
                     panic!("server failed");
                 }
+                PushFailed(error) => {
+                    // Unlike a bound-address failure, a flaky remote-write peer
+                    // must not take the node down; just report and retry next
+                    // tick.
+                    error!(error = %&**error, "failed to push metrics");
+                }
             });
         }
     }
@@ -93,7 +137,7 @@ fn start_server(ctx: &Context<Config>) -> JoinHandle<()> {
     use hyper::{
         server::{conn::AddrStream, Server},
         service::{make_service_fn, service_fn},
-        Body, Error as HyperError, Response,
+        Body, Error as HyperError, Request, Response,
     };
 
     let address = ctx.config().address;
@@ -110,21 +154,38 @@ fn start_server(ctx: &Context<Config>) -> JoinHandle<()> {
             let scope = scope.clone();
 
             async move {
-                Ok::<_, HyperError>(service_fn(move |_| {
+                Ok::<_, HyperError>(service_fn(move |req: Request<Body>| {
                     let ctx = ctx.clone();
                     let scope = scope.clone();
 
+                    // Inherit the caller's trace (and link to its span) if it
+                    // sent a `traceparent` header, otherwise root a fresh
+                    // trace here.
+                    let (trace_id, parent_span) = req
+                        .headers()
+                        .get("traceparent")
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(parse_traceparent)
+                        .unwrap_or_else(|| (trace_id::generate(), None));
+
                     let f = async move {
-                        let output = ctx
+                        let rendered = ctx
                             .request(Render)
                             .from(ctx.addr())
                             .resolve()
                             .await
                             .expect("failed to send to the telemeter");
-                        Ok::<_, HyperError>(Response::new(Body::from(output)))
+                        let response = Response::builder()
+                            .header("content-type", rendered.content_type)
+                            .body(Body::from(rendered.body))
+                            .expect("invalid response");
+                        Ok::<_, HyperError>(response)
                     };
 
-                    scope.set_trace_id(trace_id::generate());
+                    scope.set_trace_id(trace_id);
+                    if let Some(parent_span) = parent_span {
+                        scope.set_parent_span(parent_span);
+                    }
                     scope.within(f)
                 }))
             }
@@ -132,6 +193,26 @@ fn start_server(ctx: &Context<Config>) -> JoinHandle<()> {
         server.serve(make_svc).await
     };
 
+    /// Extracts the trace id and parent span id from a W3C `traceparent`
+    /// header value (`00-{trace_id}-{span_id}-{flags}`), taking the low 64
+    /// bits of the 128-bit `trace-id` field to match elfo's representation.
+    /// The span id is `None` if it fails to parse, so the scrape request is
+    /// still linked by trace id, just rooted rather than a child span.
+    fn parse_traceparent(value: &str) -> Option<(trace_id::TraceId, Option<scope::SpanId>)> {
+        let mut parts = value.split('-');
+        let _version = parts.next()?;
+        let trace = parts.next()?;
+        let span = parts.next();
+
+        let low = u64::from_str_radix(&trace[trace.len().saturating_sub(16)..], 16).ok()?;
+        let trace_id = trace_id::TraceId::try_from(low).ok()?;
+        let parent_span = span
+            .and_then(|span| u64::from_str_radix(span, 16).ok())
+            .and_then(|raw| scope::SpanId::try_from(raw).ok());
+
+        Some((trace_id, parent_span))
+    }
+
     tokio::spawn(async move {
         if let Err(err) = serving.await {
             let f = async {
@@ -142,4 +223,128 @@ fn start_server(ctx: &Context<Config>) -> JoinHandle<()> {
             scope1.within(f).await;
         }
     })
+}
+
+/// Pushes a rendered snapshot to a remote-write/OTLP endpoint.
+///
+/// Runs in a detached task so a slow peer never stalls the actor loop; a
+/// delivery failure is reported as a routable [`PushFailed`] (mirroring
+/// [`ServerFailed`]) instead of panicking, so the node keeps scraping/pushing.
+fn push(ctx: &Context<Config>, endpoint: String, rendered: Rendered) {
+    use hyper::{Body, Client, Method, Request};
+
+    let endpoint = match endpoint.parse::<hyper::Uri>() {
+        Ok(endpoint) => endpoint,
+        Err(error) => {
+            error!(%error, "invalid push endpoint, skipping");
+            return;
+        }
+    };
+
+    let ctx = ctx.pruned();
+    let scope = scope::expose();
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(endpoint)
+        .header("content-type", rendered.content_type)
+        .header("traceparent", scope.traceparent())
+        .body(Body::from(rendered.body))
+        .expect("invalid push request");
+
+    tokio::spawn(async move {
+        if let Err(err) = Client::new().request(request).await {
+            let f = async {
+                let _ = ctx.send(PushFailed(Arc::new(Local::from(err)))).await;
+            };
+            scope.within(f).await;
+        }
+    });
+}
+
+/// Converts the Prometheus text produced by [`Renderer`] into OpenMetrics:
+/// counters gain the mandatory `_total` suffix and the document is terminated
+/// with `# EOF`. The `# TYPE`/`# HELP` metadata the renderer already emits is
+/// preserved as-is.
+fn to_openmetrics(prometheus: &str) -> String {
+    use std::collections::HashSet;
+
+    let counters: HashSet<&str> = prometheus
+        .lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix("# TYPE ")?;
+            let mut parts = rest.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some(name), Some("counter")) => Some(name),
+                _ => None,
+            }
+        })
+        .collect();
+
+    let mut out = String::with_capacity(prometheus.len() + "# EOF\n".len());
+    for line in prometheus.lines() {
+        if line.is_empty() || line.starts_with('#') {
+            out.push_str(line);
+        } else {
+            // `name{labels} value [ts]`: suffix the series name of a counter.
+            let name_end = line.find(['{', ' ']).unwrap_or(line.len());
+            let name = &line[..name_end];
+            out.push_str(name);
+            if counters.contains(name) && !name.ends_with("_total") {
+                out.push_str("_total");
+            }
+            out.push_str(&line[name_end..]);
+        }
+        out.push('\n');
+    }
+    out.push_str("# EOF\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suffixes_counter_series_with_total() {
+        let prometheus = "\
+# TYPE requests counter
+# HELP requests total requests handled
+requests{method=\"GET\"} 42
+# TYPE latency gauge
+latency 0.5
+";
+
+        let expected = "\
+# TYPE requests counter
+# HELP requests total requests handled
+requests_total{method=\"GET\"} 42
+# TYPE latency gauge
+latency 0.5
+# EOF
+";
+
+        assert_eq!(to_openmetrics(prometheus), expected);
+    }
+
+    #[test]
+    fn leaves_an_already_suffixed_counter_alone() {
+        let prometheus = "\
+# TYPE requests_total counter
+requests_total 1
+";
+
+        let expected = "\
+# TYPE requests_total counter
+requests_total 1
+# EOF
+";
+
+        assert_eq!(to_openmetrics(prometheus), expected);
+    }
+
+    #[test]
+    fn appends_eof_even_for_empty_input() {
+        assert_eq!(to_openmetrics(""), "# EOF\n");
+    }
 }
\ No newline at end of file
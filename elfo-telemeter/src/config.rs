@@ -0,0 +1,37 @@
+use std::{net::SocketAddr, time::Duration};
+
+use serde::Deserialize;
+
+/// Which text exposition format the telemeter renders and pushes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    /// Classic Prometheus text exposition.
+    #[default]
+    Prometheus,
+    /// OpenMetrics text exposition: explicit `# TYPE`/`# HELP` lines, `_total`
+    /// suffixes on counters and a trailing `# EOF`, so scrapers can consume it
+    /// unambiguously.
+    OpenMetrics,
+}
+
+/// The telemeter configuration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    /// The address to serve the scrape endpoint on.
+    pub address: SocketAddr,
+    /// How often stored metrics are compacted (and, in push mode, shipped).
+    #[serde(default = "default_compaction_interval")]
+    pub compaction_interval: Duration,
+    /// The text exposition format used for both scraping and pushing.
+    #[serde(default)]
+    pub format: Format,
+    /// If set, each snapshot is also pushed to this OTLP/remote-write endpoint
+    /// on the `compaction_interval` cadence instead of waiting to be scraped.
+    #[serde(default)]
+    pub push_endpoint: Option<String>,
+}
+
+fn default_compaction_interval() -> Duration {
+    Duration::from_secs(5)
+}